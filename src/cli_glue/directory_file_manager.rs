@@ -0,0 +1,123 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    fs,
+    hash::{Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+};
+
+use typst::syntax::VirtualPath;
+
+use super::file_manager::{parse_package, DirectoryEntry, FileManager, FileManagerError};
+
+/// A file manager that reads the project from a directory and keeps packages
+/// in a cache directory, as `namespace/name/version`.
+///
+/// Paths that escape the project or package directory are refused, including
+/// through symbolic links, which are only followed within the directory.
+pub struct DirectoryFileManager {
+    root: PathBuf,
+    package_cache: PathBuf,
+}
+
+impl DirectoryFileManager {
+    pub fn new(root: String, package_cache: String) -> Self {
+        Self {
+            root: root.into(),
+            package_cache: package_cache.into(),
+        }
+    }
+
+    fn resolve(&self, path: &str, package: Option<&str>) -> Result<PathBuf, FileManagerError> {
+        let base = match package {
+            Some(package) => {
+                let spec = parse_package(package).ok_or(FileManagerError::NotFound)?;
+
+                self.package_cache
+                    .join(spec.namespace.as_str())
+                    .join(spec.name.as_str())
+                    .join(spec.version.to_string())
+            }
+            None => self.root.clone(),
+        };
+
+        let path = VirtualPath::new(path)
+            .resolve(&base)
+            .ok_or(FileManagerError::AccessDenied)?;
+
+        if !canonicalize_existing(&path)?.starts_with(canonicalize_existing(&base)?) {
+            return Err(FileManagerError::AccessDenied);
+        }
+
+        Ok(path)
+    }
+}
+
+/// Canonicalizes the deepest ancestor of `path` that exists, so that paths
+/// about to be created can be checked too. Fails for dangling links.
+fn canonicalize_existing(path: &Path) -> io::Result<PathBuf> {
+    let mut ancestor = path;
+    while fs::symlink_metadata(ancestor).is_err() {
+        match ancestor.parent() {
+            Some(parent) => ancestor = parent,
+            None => break,
+        }
+    }
+
+    ancestor.canonicalize()
+}
+
+impl FileManager for DirectoryFileManager {
+    fn read(&self, path: String, package: Option<String>) -> Result<Vec<u8>, FileManagerError> {
+        let path = self.resolve(&path, package.as_deref())?;
+        if path.is_dir() {
+            return Err(FileManagerError::IsDirectory);
+        }
+
+        Ok(fs::read(path)?)
+    }
+
+    fn write(&self, path: String, package: String, data: Vec<u8>) -> Result<(), FileManagerError> {
+        let path = self.resolve(&path, Some(&package))?;
+        Ok(fs::write(path, data)?)
+    }
+
+    fn exists(&self, path: String, package: String) -> Result<bool, FileManagerError> {
+        Ok(self.resolve(&path, Some(&package))?.exists())
+    }
+
+    fn create_directory(&self, path: String, package: String) -> Result<(), FileManagerError> {
+        let path = self.resolve(&path, Some(&package))?;
+        Ok(fs::create_dir_all(path)?)
+    }
+
+    fn fingerprint(
+        &self,
+        path: String,
+        package: Option<String>,
+    ) -> Result<Option<u64>, FileManagerError> {
+        let metadata = fs::metadata(self.resolve(&path, package.as_deref())?)?;
+
+        let mut state = DefaultHasher::new();
+        metadata.modified()?.hash(&mut state);
+        metadata.len().hash(&mut state);
+        Ok(Some(state.finish()))
+    }
+
+    fn list_directory(
+        &self,
+        path: String,
+        package: Option<String>,
+    ) -> Result<Vec<DirectoryEntry>, FileManagerError> {
+        let path = self.resolve(&path, package.as_deref())?;
+
+        Ok(fs::read_dir(path)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| DirectoryEntry {
+                name: entry.file_name().to_string_lossy().to_string(),
+                // Links are not followed, so that walking the project can't loop.
+                is_directory: entry.file_type().is_ok_and(|t| t.is_dir()),
+            })
+            .collect())
+    }
+}
@@ -1,10 +1,12 @@
 use std::{
     error::Error,
     fmt::{Debug, Display},
+    io::{self, ErrorKind},
     path::PathBuf,
+    sync::Arc,
 };
 
-use typst::diag::FileError;
+use typst::{diag::FileError, syntax::package::PackageSpec};
 
 pub enum FileManagerError {
     NotFound,
@@ -59,15 +61,90 @@ impl From<FileManagerError> for FileError {
     }
 }
 
+impl From<io::Error> for FileManagerError {
+    fn from(val: io::Error) -> Self {
+        match val.kind() {
+            ErrorKind::NotFound => FileManagerError::NotFound,
+            ErrorKind::PermissionDenied => FileManagerError::AccessDenied,
+            ErrorKind::IsADirectory => FileManagerError::IsDirectory,
+            _ => FileManagerError::Other,
+        }
+    }
+}
+
 impl From<uniffi::UnexpectedUniFFICallbackError> for FileManagerError {
     fn from(_: uniffi::UnexpectedUniFFICallbackError) -> Self {
         FileManagerError::FfiCallbackError
     }
 }
 
+/// Strips the leading `@` of a package, as packages are passed both as
+/// `@preview/name:1.0.0` and as `preview/name:1.0.0`.
+pub(crate) fn normalize_package(package: &str) -> &str {
+    package.strip_prefix('@').unwrap_or(package)
+}
+
+/// Parses a package specification, with or without the leading `@`.
+pub(crate) fn parse_package(package: &str) -> Option<PackageSpec> {
+    format!("@{}", normalize_package(package)).parse().ok()
+}
+
+pub struct DirectoryEntry {
+    pub name: String,
+    pub is_directory: bool,
+}
+
 pub trait FileManager: Send + Sync {
     fn read(&self, path: String, package: Option<String>) -> Result<Vec<u8>, FileManagerError>;
     fn write(&self, path: String, package: String, data: Vec<u8>) -> Result<(), FileManagerError>;
     fn exists(&self, path: String, package: String) -> Result<bool, FileManagerError>;
     fn create_directory(&self, path: String, package: String) -> Result<(), FileManagerError>;
+    /// A cheap value that changes whenever the file does, such as its
+    /// modification time. `None` if unknown.
+    fn fingerprint(
+        &self,
+        path: String,
+        package: Option<String>,
+    ) -> Result<Option<u64>, FileManagerError>;
+    /// The entries of a directory. Symbolic links to directories should not
+    /// be reported as directories, as they may form cycles.
+    fn list_directory(
+        &self,
+        path: String,
+        package: Option<String>,
+    ) -> Result<Vec<DirectoryEntry>, FileManagerError>;
+}
+
+impl<T: FileManager + ?Sized> FileManager for Arc<T> {
+    fn read(&self, path: String, package: Option<String>) -> Result<Vec<u8>, FileManagerError> {
+        (**self).read(path, package)
+    }
+
+    fn write(&self, path: String, package: String, data: Vec<u8>) -> Result<(), FileManagerError> {
+        (**self).write(path, package, data)
+    }
+
+    fn exists(&self, path: String, package: String) -> Result<bool, FileManagerError> {
+        (**self).exists(path, package)
+    }
+
+    fn create_directory(&self, path: String, package: String) -> Result<(), FileManagerError> {
+        (**self).create_directory(path, package)
+    }
+
+    fn fingerprint(
+        &self,
+        path: String,
+        package: Option<String>,
+    ) -> Result<Option<u64>, FileManagerError> {
+        (**self).fingerprint(path, package)
+    }
+
+    fn list_directory(
+        &self,
+        path: String,
+        package: Option<String>,
+    ) -> Result<Vec<DirectoryEntry>, FileManagerError> {
+        (**self).list_directory(path, package)
+    }
 }
@@ -0,0 +1,282 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Component, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        RwLock,
+    },
+};
+
+use typst::syntax::VirtualPath;
+
+use super::file_manager::{normalize_package, DirectoryEntry, FileManager, FileManagerError};
+
+/// A file, or a directory, in the project or in a package.
+type Key = (Option<String>, PathBuf);
+
+struct File {
+    data: Vec<u8>,
+    /// Bumped on every write, and used as the file's fingerprint.
+    generation: u64,
+}
+
+/// A file manager that keeps all files in memory, for tests and sandboxed
+/// previews. Directories exist implicitly as soon as they contain a file.
+#[derive(Default)]
+pub struct InMemoryFileManager {
+    files: RwLock<HashMap<Key, File>>,
+    directories: RwLock<HashSet<Key>>,
+    /// The last generation given to a file, shared by all files so that one
+    /// that is removed and inserted again never reuses a fingerprint.
+    generation: AtomicU64,
+}
+
+impl InMemoryFileManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn key(path: &str, package: Option<&str>) -> Key {
+        let package = package.map(|p| normalize_package(p).to_string());
+        (
+            package,
+            VirtualPath::new(path).as_rooted_path().to_path_buf(),
+        )
+    }
+
+    pub fn insert(
+        &self,
+        path: String,
+        package: Option<String>,
+        data: Vec<u8>,
+    ) -> Result<(), FileManagerError> {
+        let key = Self::key(&path, package.as_deref());
+        let generation = self.generation.fetch_add(1, Ordering::Relaxed) + 1;
+
+        self.files
+            .write()
+            .map_err(|_| FileManagerError::Other)?
+            .insert(key, File { data, generation });
+        Ok(())
+    }
+
+    pub fn remove(&self, path: String, package: Option<String>) -> Result<(), FileManagerError> {
+        let key = Self::key(&path, package.as_deref());
+        self.files
+            .write()
+            .map_err(|_| FileManagerError::Other)?
+            .remove(&key);
+        Ok(())
+    }
+
+    /// Lists the paths of all files in the project, or in a package.
+    pub fn list(&self, package: Option<String>) -> Result<Vec<String>, FileManagerError> {
+        let (package, _) = Self::key("/", package.as_deref());
+
+        let mut paths = self
+            .files
+            .read()
+            .map_err(|_| FileManagerError::Other)?
+            .keys()
+            .filter(|(p, _)| *p == package)
+            .map(|(_, path)| path.to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// The direct children of a directory, with whether they are directories.
+    fn children(
+        &self,
+        (package, directory): &Key,
+    ) -> Result<BTreeMap<String, bool>, FileManagerError> {
+        let files = self.files.read().map_err(|_| FileManagerError::Other)?;
+        let directories = self
+            .directories
+            .read()
+            .map_err(|_| FileManagerError::Other)?;
+
+        let mut children = BTreeMap::new();
+        for (p, path) in files.keys().chain(directories.iter()) {
+            if p != package {
+                continue;
+            }
+
+            let Ok(relative) = path.strip_prefix(directory) else {
+                continue;
+            };
+
+            let mut components = relative.components();
+            if let Some(Component::Normal(name)) = components.next() {
+                let is_directory =
+                    components.next().is_some() || directories.contains(&(p.clone(), path.clone()));
+                *children
+                    .entry(name.to_string_lossy().to_string())
+                    .or_default() |= is_directory;
+            }
+        }
+
+        Ok(children)
+    }
+
+    fn is_directory(&self, key: &Key) -> Result<bool, FileManagerError> {
+        let explicit = self
+            .directories
+            .read()
+            .map_err(|_| FileManagerError::Other)?
+            .contains(key);
+
+        Ok(explicit || !self.children(key)?.is_empty())
+    }
+}
+
+impl FileManager for InMemoryFileManager {
+    fn read(&self, path: String, package: Option<String>) -> Result<Vec<u8>, FileManagerError> {
+        let key = Self::key(&path, package.as_deref());
+        let data = self
+            .files
+            .read()
+            .map_err(|_| FileManagerError::Other)?
+            .get(&key)
+            .map(|file| file.data.clone());
+
+        match data {
+            Some(data) => Ok(data),
+            None if self.is_directory(&key)? => Err(FileManagerError::IsDirectory),
+            None => Err(FileManagerError::NotFound),
+        }
+    }
+
+    fn write(&self, path: String, package: String, data: Vec<u8>) -> Result<(), FileManagerError> {
+        self.insert(path, Some(package), data)
+    }
+
+    fn exists(&self, path: String, package: String) -> Result<bool, FileManagerError> {
+        let key = Self::key(&path, Some(&package));
+        let exists = self
+            .files
+            .read()
+            .map_err(|_| FileManagerError::Other)?
+            .contains_key(&key);
+
+        Ok(exists || self.is_directory(&key)?)
+    }
+
+    fn create_directory(&self, path: String, package: String) -> Result<(), FileManagerError> {
+        let key = Self::key(&path, Some(&package));
+        self.directories
+            .write()
+            .map_err(|_| FileManagerError::Other)?
+            .insert(key);
+        Ok(())
+    }
+
+    fn fingerprint(
+        &self,
+        path: String,
+        package: Option<String>,
+    ) -> Result<Option<u64>, FileManagerError> {
+        let key = Self::key(&path, package.as_deref());
+        let generation = self
+            .files
+            .read()
+            .map_err(|_| FileManagerError::Other)?
+            .get(&key)
+            .map(|file| file.generation);
+
+        match generation {
+            Some(generation) => Ok(Some(generation)),
+            None if self.is_directory(&key)? => Err(FileManagerError::IsDirectory),
+            None => Err(FileManagerError::NotFound),
+        }
+    }
+
+    fn list_directory(
+        &self,
+        path: String,
+        package: Option<String>,
+    ) -> Result<Vec<DirectoryEntry>, FileManagerError> {
+        let key = Self::key(&path, package.as_deref());
+
+        Ok(self
+            .children(&key)?
+            .into_iter()
+            .map(|(name, is_directory)| DirectoryEntry { name, is_directory })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InMemoryFileManager;
+    use crate::{FileManager, FileManagerError};
+
+    const DATA: &[u8] = b"data";
+
+    fn file_manager(paths: &[&str]) -> InMemoryFileManager {
+        let file_manager = InMemoryFileManager::new();
+        for path in paths {
+            file_manager
+                .insert(path.to_string(), None, DATA.to_vec())
+                .unwrap();
+        }
+
+        file_manager
+    }
+
+    #[test]
+    fn reads_and_lists_files() {
+        let file_manager = file_manager(&["/main.typ", "ch/one.typ"]);
+
+        assert_eq!(file_manager.read("/ch/one.typ".into(), None).unwrap(), DATA);
+        assert!(matches!(
+            file_manager.read("/ch".into(), None),
+            Err(FileManagerError::IsDirectory)
+        ));
+        assert!(matches!(
+            file_manager.read("/missing.typ".into(), None),
+            Err(FileManagerError::NotFound)
+        ));
+
+        let entries = file_manager.list_directory("/".into(), None).unwrap();
+        let entries = entries
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry.is_directory))
+            .collect::<Vec<_>>();
+        assert_eq!(entries, [("ch", true), ("main.typ", false)]);
+
+        file_manager.remove("/ch/one.typ".into(), None).unwrap();
+        assert_eq!(file_manager.list(None).unwrap(), ["/main.typ"]);
+    }
+
+    #[test]
+    fn accepts_packages_with_and_without_at() {
+        let file_manager = InMemoryFileManager::new();
+        file_manager
+            .write("/lib.typ".into(), "@preview/x:1.0.0".into(), DATA.to_vec())
+            .unwrap();
+
+        let data = file_manager.read("/lib.typ".into(), Some("preview/x:1.0.0".into()));
+        assert_eq!(data.unwrap(), DATA);
+    }
+
+    #[test]
+    fn fingerprints_change_on_every_write() {
+        let file_manager = file_manager(&["/main.typ"]);
+        let fingerprint = || file_manager.fingerprint("/main.typ".into(), None).unwrap();
+
+        let first = fingerprint();
+        file_manager
+            .insert("/main.typ".into(), None, DATA.to_vec())
+            .unwrap();
+        let second = fingerprint();
+        file_manager.remove("/main.typ".into(), None).unwrap();
+        file_manager
+            .insert("/main.typ".into(), None, DATA.to_vec())
+            .unwrap();
+
+        assert_ne!(first, second);
+        assert_ne!(second, fingerprint());
+    }
+}
@@ -1,5 +1,7 @@
+pub mod directory_file_manager;
 pub mod file_manager;
 pub mod fonts;
+pub mod in_memory_file_manager;
 mod package;
 mod path_hash;
 mod path_slot;
@@ -17,6 +17,7 @@ pub struct PathSlot {
     package: Option<PackageSpec>,
     source: OnceCell<FileResult<Source>>,
     buffer: OnceCell<FileResult<Bytes>>,
+    fingerprint: Option<u64>,
 }
 
 impl PathSlot {
@@ -27,6 +28,7 @@ impl PathSlot {
             package: package.cloned(),
             source: OnceCell::new(),
             buffer: OnceCell::new(),
+            fingerprint: None,
         }
     }
 
@@ -64,6 +66,37 @@ impl PathSlot {
             .clone()
     }
 
+    #[allow(clippy::borrowed_box)]
+    fn current_fingerprint(&self, reader: &Box<dyn FileManager>) -> Option<u64> {
+        reader
+            .fingerprint(
+                self.path.to_str().unwrap().to_owned(),
+                self.package_string(),
+            )
+            .ok()
+            .flatten()
+    }
+
+    /// Remember the fingerprint of the file, before its contents are read.
+    #[allow(clippy::borrowed_box)]
+    pub fn record_fingerprint(&mut self, reader: &Box<dyn FileManager>) {
+        self.fingerprint = self.current_fingerprint(reader);
+    }
+
+    /// Forget the contents of the file if its fingerprint changed, or if it
+    /// is unknown.
+    #[allow(clippy::borrowed_box)]
+    pub fn refresh(&mut self, reader: &Box<dyn FileManager>) {
+        let fingerprint = self.current_fingerprint(reader);
+
+        if fingerprint.is_none() || fingerprint != self.fingerprint {
+            self.source = OnceCell::new();
+            self.buffer = OnceCell::new();
+        }
+
+        self.fingerprint = fingerprint;
+    }
+
     /// Decode UTF-8 with an optional BOM.
     fn decode_utf8(buf: Vec<u8>) -> FileResult<String> {
         Ok(if buf.starts_with(b"\xef\xbb\xbf") {
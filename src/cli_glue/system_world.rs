@@ -1,21 +1,26 @@
 use std::{
     cell::{RefCell, RefMut},
-    collections::HashMap,
-    path::PathBuf,
+    collections::{HashMap, HashSet},
+    ops::Range,
+    path::{Path, PathBuf},
 };
 
-use chrono::Datelike;
+use chrono::{DateTime, Datelike, Utc};
 use comemo::Prehashed;
 use once_cell::unsync::OnceCell;
 use typst::{
     diag::{FileError, FileResult},
-    foundations::{Bytes, Datetime},
+    foundations::{Bytes, Dict},
     syntax::{FileId, Source, VirtualPath},
     text::{Font, FontBook},
     Library, World,
 };
 
-use super::{file_manager::FileManager, path_hash::PathHash, path_slot::PathSlot};
+use super::{
+    file_manager::{DirectoryEntry, FileManager},
+    path_hash::PathHash,
+    path_slot::PathSlot,
+};
 
 use crate::st_log;
 
@@ -27,12 +32,25 @@ pub struct SystemWorld {
     pub(crate) fonts: HashMap<usize, Font>,
     hashes: RefCell<HashMap<FileId, FileResult<PathHash>>>,
     paths: RefCell<HashMap<PathHash, PathSlot>>,
-    today: OnceCell<Option<Datetime>>,
+    /// Unsaved contents set by the host, which take precedence over the file
+    /// manager and survive resets.
+    overlays: HashMap<FileId, Source>,
+    /// The files accessed since the last call to `clear_dependencies`.
+    dependencies: RefCell<HashSet<FileId>>,
+    /// Whether to ask the file manager for fingerprints instead of relying on
+    /// change notifications.
+    fingerprinting: bool,
+    /// The current instant, read once per compilation cycle.
+    now: OnceCell<DateTime<Utc>>,
+    /// An instant set by the host for reproducible output.
+    pinned_now: Option<DateTime<Utc>>,
 
     // Custom file reader for asking the main program to read files in a sandboxed manner.
     pub(crate) file_manager: Box<dyn FileManager>,
 }
 
+// The caches use `RefCell`s and unsynchronized `OnceCell`s, so anything that
+// reads files, or the clock, must hold the world's write lock.
 unsafe impl Sync for SystemWorld {}
 
 impl SystemWorld {
@@ -47,8 +65,12 @@ impl SystemWorld {
             fonts: HashMap::new(),
             hashes: RefCell::default(),
             paths: RefCell::default(),
+            overlays: HashMap::new(),
+            dependencies: RefCell::default(),
+            fingerprinting: false,
             main: FileId::new(None, vpath),
-            today: OnceCell::new(),
+            now: OnceCell::new(),
+            pinned_now: None,
             file_manager,
         }
     }
@@ -75,6 +97,12 @@ impl World for SystemWorld {
 
     fn source(&self, id: FileId) -> FileResult<Source> {
         st_log!("Getting source for file {:?}.", id);
+        self.record_dependency(id);
+
+        if let Some(source) = self.overlays.get(&id) {
+            return Ok(source.clone());
+        }
+
         self.slot(id)?.source(&self.file_manager)
     }
 
@@ -90,25 +118,35 @@ impl World for SystemWorld {
 
     fn file(&self, id: FileId) -> FileResult<Bytes> {
         st_log!("Getting file {:?}.", id);
+        self.record_dependency(id);
+
+        if let Some(source) = self.overlays.get(&id) {
+            return Ok(Bytes::from(source.text().as_bytes().to_vec()));
+        }
+
         self.slot(id)?.file(&self.file_manager)
     }
 
     fn today(&self, offset: Option<i64>) -> Option<typst::foundations::Datetime> {
         st_log!("Getting today's date.");
-        *self.today.get_or_init(|| {
-            let naive = match offset {
-                None => chrono::Local::now().naive_local(),
-                Some(o) => {
-                    (chrono::Utc::now() + chrono::Duration::try_hours(o).unwrap()).naive_utc()
-                }
-            };
+        let now = self
+            .now
+            .get_or_init(|| self.pinned_now.unwrap_or_else(Utc::now));
+
+        let naive = match offset {
+            // A pinned instant must not depend on the machine's time zone.
+            None if self.pinned_now.is_some() => now.naive_utc(),
+            None => now.with_timezone(&chrono::Local).naive_local(),
+            Some(o) => now
+                .checked_add_signed(chrono::Duration::try_hours(o)?)?
+                .naive_utc(),
+        };
 
-            typst::foundations::Datetime::from_ymd(
-                naive.year(),
-                naive.month().try_into().ok()?,
-                naive.day().try_into().ok()?,
-            )
-        })
+        typst::foundations::Datetime::from_ymd(
+            naive.year(),
+            naive.month().try_into().ok()?,
+            naive.day().try_into().ok()?,
+        )
     }
 }
 
@@ -139,16 +177,192 @@ impl SystemWorld {
                 .map_err(|_| FileError::Other(Some("paths BorrowMut error".into())))?,
             |paths| {
                 paths.entry(hash).or_insert_with(|| {
-                    PathSlot::new(id, id.vpath().as_rooted_path().into(), &id.package())
+                    let mut slot =
+                        PathSlot::new(id, id.vpath().as_rooted_path().into(), &id.package());
+                    if self.fingerprinting {
+                        slot.record_fingerprint(&self.file_manager);
+                    }
+                    slot
                 })
             },
         ))
     }
 
+    fn record_dependency(&self, id: FileId) {
+        if let Ok(mut dependencies) = self.dependencies.try_borrow_mut() {
+            dependencies.insert(id);
+        }
+    }
+
+    pub fn clear_dependencies(&self) {
+        if let Ok(mut dependencies) = self.dependencies.try_borrow_mut() {
+            dependencies.clear();
+        }
+    }
+
+    pub fn dependencies(&self) -> Vec<FileId> {
+        self.dependencies.borrow().iter().copied().collect()
+    }
+
+    /// Lists a directory of the project, or nothing if it can't be read.
+    pub fn list_directory(&self, path: &Path) -> Vec<DirectoryEntry> {
+        match self
+            .file_manager
+            .list_directory(path.to_string_lossy().to_string(), None)
+        {
+            Ok(entries) => entries,
+            Err(e) => {
+                st_log!("Error listing directory {:?}: {}.", path, e);
+                vec![]
+            }
+        }
+    }
+
+    /// Finds the project's files with one of the given extensions,
+    /// recursively. Hidden entries are skipped, and so are directories nested
+    /// deeper than `MAX_DEPTH`, which also stops symbolic link cycles.
+    pub fn find_files(&self, extensions: &[&str]) -> Vec<PathBuf> {
+        const MAX_DEPTH: usize = 8;
+
+        let mut files = vec![];
+        let mut directories = vec![(PathBuf::from("/"), 0)];
+
+        while let Some((directory, depth)) = directories.pop() {
+            for entry in self.list_directory(&directory) {
+                if entry.name.starts_with('.') {
+                    continue;
+                }
+
+                let path = directory.join(&entry.name);
+                if entry.is_directory {
+                    if depth < MAX_DEPTH {
+                        directories.push((path, depth + 1));
+                    }
+                } else if path
+                    .extension()
+                    .is_some_and(|extension| extensions.iter().any(|e| extension == *e))
+                {
+                    files.push(path);
+                }
+            }
+        }
+
+        files.sort();
+        files
+    }
+
+    pub fn set_fingerprinting(&mut self, enabled: bool) {
+        st_log!("Setting fingerprinting to {}.", enabled);
+
+        // Slots read so far have no fingerprint to compare against.
+        self.fingerprinting = enabled;
+        self.hashes.get_mut().clear();
+        self.paths.get_mut().clear();
+    }
+
+    /// Forgets the contents of files whose fingerprint changed, when
+    /// fingerprinting is enabled.
+    pub fn refresh(&self) {
+        if !self.fingerprinting {
+            return;
+        }
+
+        st_log!("Refreshing changed files.");
+        if let Ok(mut paths) = self.paths.try_borrow_mut() {
+            for slot in paths.values_mut() {
+                slot.refresh(&self.file_manager);
+            }
+        }
+    }
+
+    /// Starts a new compilation cycle, in which the clock is read again.
+    pub fn tick(&mut self) {
+        self.now = OnceCell::new();
+    }
+
     pub fn reset(&mut self) {
         st_log!("Resetting system world.");
         self.hashes.borrow_mut().clear();
         self.paths.borrow_mut().clear();
+        self.now = OnceCell::new();
+    }
+
+    /// Forgets the cached contents of a single file. Unsaved contents are
+    /// kept.
+    pub fn invalidate(&mut self, id: FileId) {
+        st_log!("Invalidating file {:?}.", id);
+
+        self.hashes.borrow_mut().remove(&id);
+        if let Ok(hash) = PathHash::new(id.vpath().as_rooted_path(), &id.package()) {
+            self.paths.borrow_mut().remove(&hash);
+        }
+    }
+
+    /// Returns the overlay of a file, creating it from the file's current
+    /// contents if needed.
+    fn overlay(&mut self, id: FileId) -> FileResult<&mut Source> {
+        if !self.overlays.contains_key(&id) {
+            let source = self.slot(id)?.source(&self.file_manager)?;
+            self.overlays.insert(id, source);
+        }
+
+        Ok(self.overlays.get_mut(&id).unwrap())
+    }
+
+    /// Edits the unsaved contents of a file, returning the range that was
+    /// reparsed.
+    pub fn edit_source(
+        &mut self,
+        id: FileId,
+        replace: Range<usize>,
+        with: &str,
+    ) -> FileResult<Range<usize>> {
+        st_log!("Editing file {:?} at {:?}.", id, replace);
+
+        let source = self.overlay(id)?;
+        if source.text().get(replace.clone()).is_none() {
+            return Err(FileError::Other(Some("edit out of bounds".into())));
+        }
+
+        Ok(source.edit(replace, with))
+    }
+
+    /// Replaces the unsaved contents of a file, reparsing only what changed.
+    pub fn set_source_text(&mut self, id: FileId, text: &str) {
+        st_log!("Setting contents of file {:?}.", id);
+
+        match self.overlay(id) {
+            Ok(source) => {
+                source.replace(text);
+            }
+            Err(_) => {
+                self.overlays.insert(id, Source::new(id, text.into()));
+            }
+        }
+    }
+
+    /// Drops the unsaved contents of a file, so that it is read from the file
+    /// manager again.
+    pub fn clear_source_text(&mut self, id: FileId) {
+        st_log!("Clearing contents of file {:?}.", id);
+        self.overlays.remove(&id);
+    }
+
+    pub fn set_now(&mut self, now: Option<DateTime<Utc>>) {
+        st_log!("Pinning current time to {:?}.", now);
+
+        self.pinned_now = now;
+        self.now = OnceCell::new();
+    }
+
+    pub fn pinned_now(&self) -> Option<DateTime<Utc>> {
+        self.pinned_now
+    }
+
+    pub fn set_inputs(&mut self, inputs: Dict) {
+        st_log!("Setting inputs to {:?}.", inputs);
+
+        self.library = Prehashed::new(Library::builder().with_inputs(inputs).build());
     }
 
     pub fn set_main(&mut self, path: PathBuf) -> FileResult<()> {
@@ -7,7 +7,9 @@ use typst::{
 
 use typst_ide::{Completion, CompletionKind};
 
-use super::TypstCompiler;
+use crate::{utilities::fuzzy::fuzzy_score, SnippetSegment, SourceRange};
+
+use super::{path_completion::complete_path, TypstCompiler};
 
 pub enum AutocompleteKind {
     Syntax,
@@ -16,6 +18,22 @@ pub enum AutocompleteKind {
     Constant,
     Symbol,
     Type,
+    Path,
+}
+
+impl AutocompleteKind {
+    /// Breaks ties between equally good matches, lower first.
+    fn priority(&self) -> u8 {
+        match self {
+            Self::Param => 0,
+            Self::Func => 1,
+            Self::Type => 2,
+            Self::Constant => 3,
+            Self::Syntax => 4,
+            Self::Symbol => 5,
+            Self::Path => 6,
+        }
+    }
 }
 
 impl From<CompletionKind> for AutocompleteKind {
@@ -36,15 +54,30 @@ pub struct AutocompleteResult {
     pub label: String,
     pub completion: String,
     pub description: String,
+    /// The character a symbol completion resolves to.
+    pub symbol: Option<String>,
+    /// The completion, split into text and tab stops.
+    pub snippet: Vec<SnippetSegment>,
+    /// The text that the completion replaces.
+    pub replace_range: SourceRange,
 }
 
-impl From<Completion> for AutocompleteResult {
-    fn from(value: Completion) -> Self {
+impl AutocompleteResult {
+    fn new(value: Completion, replace_range: SourceRange) -> Self {
+        let completion = value.apply.unwrap_or(value.label.clone()).to_string();
+        let symbol = match value.kind {
+            CompletionKind::Symbol(c) => Some(c.to_string()),
+            _ => None,
+        };
+
         Self {
-            completion: value.apply.unwrap_or(value.label.clone()).to_string(),
+            symbol,
+            snippet: SnippetSegment::parse(&completion),
+            completion,
             label: value.label.to_string(),
             description: value.detail.unwrap_or_default().to_string(),
             kind: value.kind.into(),
+            replace_range,
         }
     }
 }
@@ -55,17 +88,20 @@ impl TypstCompiler {
         file_path: String,
         line: u64,
         column: u64,
+        explicit: bool,
+        limit: Option<u64>,
     ) -> Vec<AutocompleteResult> {
         let compiler = self.clone();
         let path = PathBuf::from(file_path.clone());
-        let Ok(mut world) = compiler.world.write() else {
+        let Ok(document) = compiler.document.read() else {
+            return vec![];
+        };
+        let Ok(world) = compiler.world.write() else {
             return vec![];
         };
 
         let vpath = VirtualPath::new(path);
 
-        world.reset();
-
         let id = FileId::new(None, vpath);
         let Ok(source) = world.source(id) else {
             return vec![];
@@ -75,13 +111,48 @@ impl TypstCompiler {
             return vec![];
         };
 
-        // TODO: Pass last document.
-        let result = typst_ide::autocomplete(&(*world), None, &source, position, false);
+        let (start, results) = match complete_path(&world, &source, position) {
+            Some(paths) => paths,
+            None => {
+                let result = typst_ide::autocomplete(
+                    &(*world),
+                    document.as_ref(),
+                    &source,
+                    position,
+                    explicit,
+                );
 
-        let Some(completions) = result else {
-            return vec![];
+                let Some((start, completions)) = result else {
+                    return vec![];
+                };
+
+                let Some(replace_range) = SourceRange::from_range(start..position, &source) else {
+                    return vec![];
+                };
+
+                let results = completions
+                    .into_iter()
+                    .map(|completion| AutocompleteResult::new(completion, replace_range.clone()))
+                    .collect();
+
+                (start, results)
+            }
         };
 
-        completions.1.into_iter().map(Into::into).collect()
+        // Rank against what has been typed so far, keeping the original order
+        // between equally good matches.
+        let prefix = &source.text()[start..position];
+        let mut ranked = results
+            .into_iter()
+            .filter_map(|result| Some((fuzzy_score(prefix, &result.label)?, result)))
+            .collect::<Vec<_>>();
+
+        ranked.sort_by_key(|(score, result)| (-score, result.kind.priority()));
+
+        ranked
+            .into_iter()
+            .map(|(_, result)| result)
+            .take(limit.map_or(usize::MAX, |limit| limit as usize))
+            .collect()
     }
 }
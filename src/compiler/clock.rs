@@ -0,0 +1,67 @@
+use std::{
+    error::Error,
+    fmt::{Debug, Display},
+};
+
+use chrono::{DateTime, Utc};
+
+use super::{pdf::timestamp_to_datetime, TypstCompiler};
+
+pub enum ClockError {
+    InvalidTimestamp,
+}
+
+impl Error for ClockError {}
+
+impl Display for ClockError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClockError::InvalidTimestamp => write!(f, "invalid timestamp"),
+        }
+    }
+}
+
+impl Debug for ClockError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+/// Converts a Unix timestamp to an instant that documents and PDF metadata
+/// can represent.
+fn instant(timestamp: i64) -> Result<DateTime<Utc>, ClockError> {
+    timestamp_to_datetime(timestamp).map_err(|_| ClockError::InvalidTimestamp)?;
+    DateTime::from_timestamp(timestamp, 0).ok_or(ClockError::InvalidTimestamp)
+}
+
+/// The instant set through `SOURCE_DATE_EPOCH`, if it is valid.
+pub(crate) fn source_date_epoch() -> Option<DateTime<Utc>> {
+    let epoch = std::env::var("SOURCE_DATE_EPOCH").ok()?;
+    instant(epoch.trim().parse().ok()?).ok()
+}
+
+impl TypstCompiler {
+    /// Pins the current time to a Unix timestamp, in the style of
+    /// `SOURCE_DATE_EPOCH`, or follows the system clock again when `None`.
+    ///
+    /// The pinned time is used for `datetime.today()` and as the default PDF
+    /// creation timestamp.
+    pub fn set_now(&self, timestamp: Option<i64>) -> Result<(), ClockError> {
+        let now = timestamp.map(instant).transpose()?;
+
+        if let Ok(mut world) = self.world.write() {
+            world.set_now(now);
+            Ok(())
+        } else {
+            panic!("Failed to lock world.")
+        }
+    }
+
+    pub(crate) fn pinned_timestamp(&self) -> Option<i64> {
+        self.world
+            .read()
+            .unwrap()
+            .pinned_now()
+            .map(|now| now.timestamp())
+    }
+}
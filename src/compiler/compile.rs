@@ -1,11 +1,17 @@
-use typst::{diag::SourceDiagnostic, eval::Tracer, syntax::Source, World};
+use typst::{
+    diag::{Severity, SourceDiagnostic},
+    eval::Tracer,
+    model::Document,
+    syntax::Source,
+    World,
+};
 
-use crate::SourceRange;
+use crate::{FileReference, SourceRange};
 
-use super::TypstCompiler;
+use super::{pdf::PdfExportOptions, TypstCompiler};
 
 pub struct CompilationError {
-    pub severity: typst::diag::Severity,
+    pub severity: Severity,
     pub source_path: Option<String>,
     pub range: Option<SourceRange>,
     pub message: String,
@@ -16,45 +22,108 @@ pub enum CompilationResult {
     Document {
         data: Vec<u8>,
         warnings: Vec<CompilationError>,
+        dependencies: Vec<FileReference>,
     },
     Errors {
         errors: Vec<CompilationError>,
+        dependencies: Vec<FileReference>,
     },
 }
 
+/// A compiled document, or the errors that prevented it, along with the files
+/// read by the compilation, sorted by package and path.
+pub(crate) struct Typeset {
+    pub result: Result<(Document, Vec<CompilationError>), Vec<CompilationError>>,
+    pub dependencies: Vec<FileReference>,
+}
+
 impl TypstCompiler {
     pub fn compile(&self) -> CompilationResult {
-        if let Ok(world) = self.world.read() {
+        let Typeset {
+            result,
+            dependencies,
+        } = self.typeset();
+
+        match result {
+            Ok((document, warnings)) => {
+                let pdf = PdfExportOptions {
+                    timestamp: self.pinned_timestamp(),
+                    ..Default::default()
+                }
+                .export(&document);
+
+                match pdf {
+                    Ok(data) => CompilationResult::Document {
+                        data,
+                        warnings,
+                        dependencies,
+                    },
+                    Err(error) => CompilationResult::Errors {
+                        errors: vec![CompilationError {
+                            severity: Severity::Error,
+                            source_path: None,
+                            range: None,
+                            message: error.to_string(),
+                            hints: vec![],
+                        }],
+                        dependencies,
+                    },
+                }
+            }
+            Err(errors) => CompilationResult::Errors {
+                errors,
+                dependencies,
+            },
+        }
+    }
+
+    /// Compiles the main file into a document, without exporting it.
+    pub(crate) fn typeset(&self) -> Typeset {
+        if let Ok(mut world) = self.world.write() {
             // world.reset();
 
+            world.tick();
+            world.refresh();
+            world.clear_dependencies();
+
             let mut tracer = Tracer::new();
 
             let result = typst::compile(&(*world), &mut tracer);
 
+            // Collected under the same lock, so that no other access is
+            // recorded in between.
+            let mut dependencies = world.dependencies();
+            dependencies
+                .sort_by_key(|id| (id.package().map(|p| p.to_string()), id.vpath().clone()));
+
             // Needed because otherwise we can't call self.diagnostic_to_error.
             drop(world);
 
-            let final_result = match result {
+            let result = match result {
                 Ok(doc) => {
-                    let pdf = typst_pdf::pdf(&doc, typst::foundations::Smart::Auto, None);
+                    if let Ok(mut document) = self.document.write() {
+                        *document = Some(doc.clone());
+                    }
+
                     let warnings = tracer.warnings();
-                    CompilationResult::Document {
-                        data: pdf,
-                        warnings: warnings
+                    Ok((
+                        doc,
+                        warnings
                             .iter()
                             .map(|e| self.diagnostic_to_error(e.clone()))
                             .collect(),
-                    }
+                    ))
                 }
-                Err(errors) => CompilationResult::Errors {
-                    errors: errors
-                        .iter()
-                        .map(|e| self.diagnostic_to_error(e.clone()))
-                        .collect(),
-                },
+                Err(errors) => Err(errors
+                    .iter()
+                    .map(|e| self.diagnostic_to_error(e.clone()))
+                    .collect()),
             };
 
-            final_result
+            Typeset {
+                result,
+                dependencies: dependencies.into_iter().map(Into::into).collect(),
+            }
         } else {
             panic!("Failed to lock world.")
         }
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use typst::model::Document;
+
+use crate::FileReference;
+
+use super::{
+    compile::{CompilationError, Typeset},
+    pdf::{ExportError, PdfExportOptions},
+    render::RenderedPage,
+    TypstCompiler,
+};
+
+pub struct PageSize {
+    pub width: f64,
+    pub height: f64,
+}
+
+/// A compiled document, exported only on demand.
+pub struct CompiledDocument {
+    pub(crate) document: Document,
+    /// The pinned time at compilation, used as the default PDF timestamp.
+    pub(crate) timestamp: Option<i64>,
+}
+
+impl CompiledDocument {
+    pub fn page_count(&self) -> u64 {
+        self.document.pages.len() as u64
+    }
+
+    /// The size of a page, in points.
+    pub fn page_size(&self, index: u64) -> Option<PageSize> {
+        let page = self.document.pages.get(index as usize)?;
+        let size = page.frame.size();
+
+        Some(PageSize {
+            width: size.x.to_pt(),
+            height: size.y.to_pt(),
+        })
+    }
+
+    pub fn export_pdf(&self) -> Result<Vec<u8>, ExportError> {
+        PdfExportOptions {
+            timestamp: self.timestamp,
+            ..Default::default()
+        }
+        .export(&self.document)
+    }
+
+    pub fn render_page(&self, index: u64, pixels_per_point: f32) -> Option<RenderedPage> {
+        let page = self.document.pages.get(index as usize)?;
+        Some(RenderedPage::from_frame(&page.frame, pixels_per_point))
+    }
+}
+
+/// The outcome of a compilation that does not export anything.
+///
+/// `document` is `None` when compilation failed, in which case `diagnostics`
+/// holds the errors. Otherwise it holds the warnings.
+pub struct DocumentCompilationResult {
+    pub document: Option<Arc<CompiledDocument>>,
+    pub diagnostics: Vec<CompilationError>,
+    pub dependencies: Vec<FileReference>,
+}
+
+impl TypstCompiler {
+    /// Compiles the main file without exporting it.
+    pub fn compile_document(&self) -> DocumentCompilationResult {
+        let Typeset {
+            result,
+            dependencies,
+        } = self.typeset();
+
+        match result {
+            Ok((document, warnings)) => DocumentCompilationResult {
+                document: Some(Arc::new(CompiledDocument {
+                    document,
+                    timestamp: self.pinned_timestamp(),
+                })),
+                diagnostics: warnings,
+                dependencies,
+            },
+            Err(errors) => DocumentCompilationResult {
+                document: None,
+                diagnostics: errors,
+                dependencies,
+            },
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use std::{ops::Range, path::PathBuf};
+
+use typst::{
+    syntax::{FileId, LinkedNode, Source, Tag, VirtualPath},
+    World,
+};
+
+use crate::SourceRange;
+
+use super::TypstCompiler;
+
+pub enum HighlightTag {
+    Comment,
+    Punctuation,
+    Escape,
+    Strong,
+    Emph,
+    Link,
+    Raw,
+    Label,
+    Ref,
+    Heading,
+    ListMarker,
+    ListTerm,
+    MathDelimiter,
+    MathOperator,
+    Keyword,
+    Operator,
+    Number,
+    String,
+    Function,
+    Interpolated,
+    Error,
+}
+
+impl From<Tag> for HighlightTag {
+    fn from(value: Tag) -> Self {
+        match value {
+            Tag::Comment => Self::Comment,
+            Tag::Punctuation => Self::Punctuation,
+            Tag::Escape => Self::Escape,
+            Tag::Strong => Self::Strong,
+            Tag::Emph => Self::Emph,
+            Tag::Link => Self::Link,
+            Tag::Raw => Self::Raw,
+            Tag::Label => Self::Label,
+            Tag::Ref => Self::Ref,
+            Tag::Heading => Self::Heading,
+            Tag::ListMarker => Self::ListMarker,
+            Tag::ListTerm => Self::ListTerm,
+            Tag::MathDelimiter => Self::MathDelimiter,
+            Tag::MathOperator => Self::MathOperator,
+            Tag::Keyword => Self::Keyword,
+            Tag::Operator => Self::Operator,
+            Tag::Number => Self::Number,
+            Tag::String => Self::String,
+            Tag::Function => Self::Function,
+            Tag::Interpolated => Self::Interpolated,
+            Tag::Error => Self::Error,
+        }
+    }
+}
+
+pub struct HighlightToken {
+    pub range: SourceRange,
+    pub tag: HighlightTag,
+}
+
+/// The highlighting of the region that was reparsed after an edit.
+pub struct HighlightUpdate {
+    pub range: SourceRange,
+    pub tokens: Vec<HighlightToken>,
+}
+
+/// Collects the highlighted leaves of `source` that overlap `range`.
+///
+/// Leaves without a tag of their own inherit the innermost tag of their
+/// ancestors, so that e.g. the text of a heading is highlighted as a heading.
+pub(crate) fn highlight_tokens(source: &Source, range: Range<usize>) -> Vec<HighlightToken> {
+    fn collect(
+        node: &LinkedNode,
+        inherited: Option<Tag>,
+        range: &Range<usize>,
+        source: &Source,
+        tokens: &mut Vec<HighlightToken>,
+    ) {
+        let node_range = node.range();
+        if node_range.end <= range.start || node_range.start >= range.end {
+            return;
+        }
+
+        let tag = typst::syntax::highlight(node).or(inherited);
+
+        if node.children().len() == 0 {
+            if let Some(tag) = tag {
+                if let Some(range) = SourceRange::from_range(node_range, source) {
+                    tokens.push(HighlightToken {
+                        range,
+                        tag: tag.into(),
+                    });
+                }
+            }
+        } else {
+            for child in node.children() {
+                collect(&child, tag, range, source, tokens);
+            }
+        }
+    }
+
+    let mut tokens = vec![];
+    collect(
+        &LinkedNode::new(source.root()),
+        None,
+        &range,
+        source,
+        &mut tokens,
+    );
+    tokens
+}
+
+impl TypstCompiler {
+    pub fn highlight(&self, file_path: String) -> Vec<HighlightToken> {
+        let Ok(world) = self.world.write() else {
+            return vec![];
+        };
+
+        let id = FileId::new(None, VirtualPath::new(PathBuf::from(file_path)));
+        let Ok(source) = world.source(id) else {
+            return vec![];
+        };
+
+        highlight_tokens(&source, 0..source.len_bytes())
+    }
+
+    pub fn highlight_range(
+        &self,
+        file_path: String,
+        start_line: u64,
+        start_column: u64,
+        end_line: u64,
+        end_column: u64,
+    ) -> Vec<HighlightToken> {
+        let Ok(world) = self.world.write() else {
+            return vec![];
+        };
+
+        let id = FileId::new(None, VirtualPath::new(PathBuf::from(file_path)));
+        let Ok(source) = world.source(id) else {
+            return vec![];
+        };
+
+        let (Some(start), Some(end)) = (
+            source.line_column_to_byte(start_line as usize, start_column as usize),
+            source.line_column_to_byte(end_line as usize, end_column as usize),
+        ) else {
+            return vec![];
+        };
+
+        highlight_tokens(&source, start..end)
+    }
+
+    /// Applies an edit exactly like `apply_edit` and returns the tokens of
+    /// the reparsed region, which replace any previous tokens in it.
+    ///
+    /// The edit is kept in the file's unsaved contents, which take precedence
+    /// over the file manager until `clear_file_contents` is called.
+    pub fn highlight_edit(
+        &self,
+        file_path: String,
+        start_line: u64,
+        start_column: u64,
+        end_line: u64,
+        end_column: u64,
+        replacement: String,
+    ) -> Option<HighlightUpdate> {
+        let (source, reparsed) = self
+            .edit(
+                file_path,
+                (start_line, start_column),
+                (end_line, end_column),
+                &replacement,
+            )
+            .ok()?;
+
+        Some(HighlightUpdate {
+            range: SourceRange::from_range(reparsed.clone(), &source)?,
+            tokens: highlight_tokens(&source, reparsed),
+        })
+    }
+}
@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+
+use typst::{
+    layout::{Abs, Point, Position},
+    syntax::{FileId, VirtualPath},
+    World,
+};
+
+use crate::SourceRange;
+
+use super::TypstCompiler;
+
+pub struct DocumentPosition {
+    pub page_index: u64,
+    pub x: f64,
+    pub y: f64,
+}
+
+impl From<Position> for DocumentPosition {
+    fn from(value: Position) -> Self {
+        Self {
+            page_index: value.page.get() as u64 - 1,
+            x: value.point.x.to_pt(),
+            y: value.point.y.to_pt(),
+        }
+    }
+}
+
+pub enum Jump {
+    Source {
+        path: String,
+        package: Option<String>,
+        range: SourceRange,
+    },
+    Url {
+        url: String,
+    },
+    Position {
+        position: DocumentPosition,
+    },
+}
+
+impl TypstCompiler {
+    /// Resolves a click on a page of the last compiled document, with
+    /// coordinates in points from the top left of the page.
+    pub fn jump_from_click(&self, page_index: u64, x: f64, y: f64) -> Option<Jump> {
+        let document = self.document.read().ok()?;
+        let document = document.as_ref()?;
+        let page = document.pages.get(page_index as usize)?;
+
+        let world = self.world.write().ok()?;
+        let click = Point::new(Abs::pt(x), Abs::pt(y));
+
+        match typst_ide::jump_from_click(&(*world), document, &page.frame, click)? {
+            typst_ide::Jump::Source(id, offset) => {
+                let source = world.source(id).ok()?;
+                Some(Jump::Source {
+                    path: id.vpath().as_rooted_path().to_string_lossy().to_string(),
+                    package: id.package().map(|p| p.to_string()),
+                    range: SourceRange::from_range(offset..offset, &source)?,
+                })
+            }
+            typst_ide::Jump::Url(url) => Some(Jump::Url {
+                url: url.to_string(),
+            }),
+            typst_ide::Jump::Position(position) => Some(Jump::Position {
+                position: position.into(),
+            }),
+        }
+    }
+
+    /// Finds the position in the last compiled document that corresponds to
+    /// a cursor in a source file.
+    pub fn jump_from_cursor(
+        &self,
+        file_path: String,
+        line: u64,
+        column: u64,
+    ) -> Option<DocumentPosition> {
+        let document = self.document.read().ok()?;
+        let document = document.as_ref()?;
+
+        let world = self.world.write().ok()?;
+        let id = FileId::new(None, VirtualPath::new(PathBuf::from(file_path)));
+        let source = world.source(id).ok()?;
+        let position = source.line_column_to_byte(line as usize, column as usize)?;
+
+        typst_ide::jump_from_cursor(document, &source, position).map(Into::into)
+    }
+}
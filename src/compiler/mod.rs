@@ -1,24 +1,68 @@
 pub mod autocomplete;
+pub mod clock;
 pub mod compile;
+pub mod document;
+pub mod highlight;
+pub mod jump;
+pub mod overlay;
+mod path_completion;
+pub mod pdf;
+pub mod preview;
+pub mod render;
+pub mod svg;
+pub mod tooltip;
 
-use std::sync::{Arc, RwLock};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
 
-use typst::diag::FileError;
+use typst::{diag::FileError, foundations::Value, model::Document};
 
-use crate::cli_glue::{file_manager::FileManager, SystemWorld};
+use crate::{
+    cli_glue::{
+        directory_file_manager::DirectoryFileManager, file_manager::FileManager,
+        in_memory_file_manager::InMemoryFileManager, SystemWorld,
+    },
+    st_log, FileReference,
+};
+
+use self::preview::PreviewCache;
 
 #[derive(Clone)]
 pub struct TypstCompiler {
     pub(crate) world: Arc<RwLock<SystemWorld>>,
+    pub(crate) preview: Arc<RwLock<PreviewCache>>,
+    /// The last successfully compiled document.
+    pub(crate) document: Arc<RwLock<Option<Document>>>,
 }
 
 impl TypstCompiler {
     pub fn new(file_manager: Box<dyn FileManager>, main: String) -> Self {
+        let mut world = SystemWorld::new(file_manager, main.into());
+        world.set_now(clock::source_date_epoch());
+
         Self {
-            world: Arc::new(RwLock::new(SystemWorld::new(file_manager, main.into()))),
+            world: Arc::new(RwLock::new(world)),
+            preview: Arc::new(RwLock::new(PreviewCache::default())),
+            document: Arc::new(RwLock::new(None)),
         }
     }
 
+    pub fn with_directory_file_manager(
+        file_manager: Arc<DirectoryFileManager>,
+        main: String,
+    ) -> Self {
+        Self::new(Box::new(file_manager), main)
+    }
+
+    pub fn with_in_memory_file_manager(
+        file_manager: Arc<InMemoryFileManager>,
+        main: String,
+    ) -> Self {
+        Self::new(Box::new(file_manager), main)
+    }
+
     pub fn set_main(&self, main: String) -> Result<(), FileError> {
         if let Ok(mut world) = self.world.write() {
             world.set_main(main.into())?;
@@ -28,7 +72,164 @@ impl TypstCompiler {
         }
     }
 
+    /// Sets the values available to documents through `sys.inputs`.
+    pub fn set_inputs(&self, inputs: HashMap<String, String>) {
+        let inputs = inputs
+            .into_iter()
+            .map(|(key, value)| (key.into(), Value::Str(value.into())))
+            .collect();
+
+        if let Ok(mut world) = self.world.write() {
+            world.set_inputs(inputs);
+        } else {
+            panic!("Failed to lock world.")
+        }
+    }
+
     pub fn notify_change(&self) {
         self.world.write().unwrap().reset();
     }
+
+    /// Lists the Typst source files of the project.
+    pub fn source_files(&self) -> Vec<String> {
+        let Ok(world) = self.world.read() else {
+            panic!("Failed to lock world.")
+        };
+
+        world
+            .find_files(&["typ"])
+            .into_iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect()
+    }
+
+    /// When enabled, files are only read again when the fingerprint reported
+    /// by the file manager changes, without needing change notifications.
+    pub fn set_fingerprinting(&self, enabled: bool) {
+        if let Ok(mut world) = self.world.write() {
+            world.set_fingerprinting(enabled);
+        } else {
+            panic!("Failed to lock world.")
+        }
+    }
+
+    /// Invalidates a single file, so that only it is read again.
+    pub fn notify_file_changed(&self, path: String, package: Option<String>) {
+        self.notify_files_changed(vec![FileReference { path, package }]);
+    }
+
+    /// Invalidates the given files, so that only they are read again.
+    pub fn notify_files_changed(&self, files: Vec<FileReference>) {
+        let Ok(mut world) = self.world.write() else {
+            panic!("Failed to lock world.")
+        };
+
+        for file in files {
+            match file.to_file_id() {
+                Some(id) => world.invalidate(id),
+                None => st_log!("Invalid package for file {}.", file.path),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::TypstCompiler;
+    use crate::InMemoryFileManager;
+
+    const ONE_PAGE: &[u8] = b"one";
+    const TWO_PAGES: &[u8] = b"one #pagebreak() two";
+
+    fn compiler(files: &[(&str, &[u8])]) -> (Arc<InMemoryFileManager>, TypstCompiler) {
+        let file_manager = Arc::new(InMemoryFileManager::new());
+        for (path, data) in files {
+            file_manager
+                .insert(path.to_string(), None, data.to_vec())
+                .unwrap();
+        }
+
+        let compiler =
+            TypstCompiler::with_in_memory_file_manager(file_manager.clone(), "main.typ".into());
+        (file_manager, compiler)
+    }
+
+    fn page_count(compiler: &TypstCompiler) -> u64 {
+        compiler
+            .compile_document()
+            .document
+            .expect("compilation failed")
+            .page_count()
+    }
+
+    #[test]
+    fn reports_dependencies() {
+        let (_, compiler) = compiler(&[
+            ("/main.typ", b"#include \"ch/one.typ\""),
+            ("/ch/one.typ", ONE_PAGE),
+            ("/unused.typ", ONE_PAGE),
+        ]);
+
+        let dependencies = compiler.compile_document().dependencies;
+        let paths = dependencies
+            .iter()
+            .map(|file| file.path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["/ch/one.typ", "/main.typ"]);
+
+        assert_eq!(
+            compiler.source_files(),
+            ["/ch/one.typ", "/main.typ", "/unused.typ"]
+        );
+    }
+
+    #[test]
+    fn rereads_files_after_notification() {
+        let (file_manager, compiler) = compiler(&[
+            ("/main.typ", b"#include \"one.typ\""),
+            ("/one.typ", ONE_PAGE),
+        ]);
+        assert_eq!(page_count(&compiler), 1);
+
+        file_manager
+            .insert("/one.typ".into(), None, TWO_PAGES.to_vec())
+            .unwrap();
+        assert_eq!(page_count(&compiler), 1);
+
+        compiler.notify_file_changed("one.typ".into(), None);
+        assert_eq!(page_count(&compiler), 2);
+    }
+
+    #[test]
+    fn rereads_files_with_changed_fingerprints() {
+        let (file_manager, compiler) = compiler(&[("/main.typ", ONE_PAGE)]);
+        compiler.set_fingerprinting(true);
+        assert_eq!(page_count(&compiler), 1);
+
+        file_manager
+            .insert("/main.typ".into(), None, TWO_PAGES.to_vec())
+            .unwrap();
+        assert_eq!(page_count(&compiler), 2);
+    }
+
+    #[test]
+    fn unsaved_contents_take_precedence_until_cleared() {
+        let (file_manager, compiler) = compiler(&[("/main.typ", ONE_PAGE)]);
+
+        compiler
+            .apply_edit("main.typ".into(), 0, 3, 0, 3, " #pagebreak()".into())
+            .unwrap();
+        assert_eq!(page_count(&compiler), 2);
+
+        file_manager
+            .insert("/main.typ".into(), None, ONE_PAGE.to_vec())
+            .unwrap();
+        compiler.notify_file_changed("main.typ".into(), None);
+        assert_eq!(page_count(&compiler), 2);
+
+        compiler.clear_file_contents("main.typ".into());
+        assert_eq!(page_count(&compiler), 1);
+    }
 }
@@ -0,0 +1,84 @@
+use std::{ops::Range, path::PathBuf};
+
+use typst::{
+    diag::FileError,
+    syntax::{FileId, Source, VirtualPath},
+    World,
+};
+
+use super::TypstCompiler;
+
+impl TypstCompiler {
+    /// Replaces a range of a file's unsaved contents. Positions refer to the
+    /// text before the edit.
+    ///
+    /// Only the affected part of the file is reparsed, and the file is no
+    /// longer read from the file manager until its contents are cleared.
+    pub fn apply_edit(
+        &self,
+        file_path: String,
+        start_line: u64,
+        start_column: u64,
+        end_line: u64,
+        end_column: u64,
+        replacement: String,
+    ) -> Result<(), FileError> {
+        self.edit(
+            file_path,
+            (start_line, start_column),
+            (end_line, end_column),
+            &replacement,
+        )?;
+        Ok(())
+    }
+
+    /// Applies an edit to the unsaved contents of a file, returning the edited
+    /// source and the byte range that was reparsed.
+    pub(crate) fn edit(
+        &self,
+        file_path: String,
+        (start_line, start_column): (u64, u64),
+        (end_line, end_column): (u64, u64),
+        replacement: &str,
+    ) -> Result<(Source, Range<usize>), FileError> {
+        let Ok(mut world) = self.world.write() else {
+            panic!("Failed to lock world.")
+        };
+
+        let id = FileId::new(None, VirtualPath::new(PathBuf::from(file_path)));
+        let source = world.source(id)?;
+
+        let (Some(start), Some(end)) = (
+            source.line_column_to_byte(start_line as usize, start_column as usize),
+            source.line_column_to_byte(end_line as usize, end_column as usize),
+        ) else {
+            return Err(FileError::Other(Some("edit out of bounds".into())));
+        };
+
+        let reparsed = world.edit_source(id, start..end, replacement)?;
+        Ok((world.source(id)?, reparsed))
+    }
+
+    /// Sets the unsaved contents of a file, which take precedence over the
+    /// file manager.
+    pub fn set_file_contents(&self, file_path: String, text: String) {
+        let id = FileId::new(None, VirtualPath::new(PathBuf::from(file_path)));
+
+        if let Ok(mut world) = self.world.write() {
+            world.set_source_text(id, &text);
+        } else {
+            panic!("Failed to lock world.")
+        }
+    }
+
+    /// Discards the unsaved contents of a file, e.g. after it was saved.
+    pub fn clear_file_contents(&self, file_path: String) {
+        let id = FileId::new(None, VirtualPath::new(PathBuf::from(file_path)));
+
+        if let Ok(mut world) = self.world.write() {
+            world.clear_source_text(id);
+        } else {
+            panic!("Failed to lock world.")
+        }
+    }
+}
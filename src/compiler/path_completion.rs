@@ -0,0 +1,130 @@
+use std::path::Path;
+
+use typst::syntax::{ast, LinkedNode, Source, SyntaxKind};
+
+use crate::{cli_glue::SystemWorld, SnippetSegment, SourceRange};
+
+use super::autocomplete::{AutocompleteKind, AutocompleteResult};
+
+/// The files that a path string may point to.
+enum Extensions {
+    Any,
+    Only(&'static [&'static str]),
+}
+
+impl Extensions {
+    fn accepts(&self, name: &str) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Only(extensions) => Path::new(name).extension().is_some_and(|extension| {
+                let extension = extension.to_string_lossy();
+                extensions.iter().any(|e| extension.eq_ignore_ascii_case(e))
+            }),
+        }
+    }
+}
+
+/// The file extensions accepted by a function that reads files, or `None`
+/// for other functions.
+fn expected_extensions(function: &str) -> Option<Extensions> {
+    Some(match function {
+        "image" => Extensions::Only(&["png", "jpg", "jpeg", "gif", "svg"]),
+        "read" => Extensions::Any,
+        "json" => Extensions::Only(&["json"]),
+        "csv" => Extensions::Only(&["csv"]),
+        "yaml" => Extensions::Only(&["yaml", "yml"]),
+        "bibliography" => Extensions::Only(&["bib", "yaml", "yml"]),
+        _ => return None,
+    })
+}
+
+/// Finds the file extensions accepted by the string `node`, if it is a path.
+fn path_string_extensions(node: &LinkedNode) -> Option<Extensions> {
+    let mut parent = node.parent()?;
+
+    match parent.kind() {
+        SyntaxKind::ModuleImport | SyntaxKind::ModuleInclude => {
+            return Some(Extensions::Only(&["typ"]))
+        }
+        // Bibliographies accept an array of paths.
+        SyntaxKind::Array => parent = parent.parent()?,
+        _ => {}
+    }
+
+    if parent.kind() != SyntaxKind::Args {
+        return None;
+    }
+
+    let call = parent.parent()?.cast::<ast::FuncCall>()?;
+    let ast::Expr::Ident(ident) = call.callee() else {
+        return None;
+    };
+
+    expected_extensions(ident.get())
+}
+
+/// Completes project paths when the cursor is in a string passed to a
+/// function that reads files, or in an import or include.
+///
+/// Returns the start of the replaced text together with the completions, or
+/// `None` when Typst should complete the string instead.
+pub(crate) fn complete_path(
+    world: &SystemWorld,
+    source: &Source,
+    cursor: usize,
+) -> Option<(usize, Vec<AutocompleteResult>)> {
+    let leaf = LinkedNode::new(source.root()).leaf_at(cursor)?;
+    if leaf.kind() != SyntaxKind::Str || cursor <= leaf.offset() || cursor >= leaf.range().end {
+        return None;
+    }
+
+    let extensions = path_string_extensions(&leaf)?;
+
+    // Packages are completed by Typst.
+    let typed = &source.text()[leaf.offset() + 1..cursor];
+    if typed.starts_with('@') {
+        return None;
+    }
+
+    // Only the last component of the typed path is replaced.
+    let (directory, start) = match typed.rfind('/') {
+        Some(index) => (&typed[..=index], leaf.offset() + 1 + index + 1),
+        None => ("", leaf.offset() + 1),
+    };
+
+    let replace_range = SourceRange::from_range(start..cursor, source)?;
+    let directory = source.id().vpath().join(directory);
+
+    let completions = world
+        .list_directory(directory.as_rooted_path())
+        .into_iter()
+        .filter(|entry| !entry.name.starts_with('.'))
+        .filter(|entry| entry.is_directory || extensions.accepts(&entry.name))
+        .map(|entry| {
+            let label = if entry.is_directory {
+                format!("{}/", entry.name)
+            } else {
+                entry.name
+            };
+
+            AutocompleteResult {
+                kind: AutocompleteKind::Path,
+                completion: label.clone(),
+                snippet: vec![SnippetSegment::Text {
+                    text: label.clone(),
+                }],
+                label,
+                description: String::new(),
+                symbol: None,
+                replace_range: replace_range.clone(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // Let Typst complete the string when there is no matching file.
+    if completions.is_empty() {
+        return None;
+    }
+
+    Some((start, completions))
+}
@@ -0,0 +1,209 @@
+use std::{
+    error::Error,
+    fmt::{Debug, Display},
+    ops::RangeInclusive,
+};
+
+use chrono::{Datelike, Timelike};
+use typst::{
+    foundations::{Datetime, Smart},
+    model::Document,
+};
+
+use super::{
+    compile::{CompilationResult, Typeset},
+    document::CompiledDocument,
+    TypstCompiler,
+};
+
+pub enum ExportError {
+    InvalidPageRange,
+    InvalidTimestamp,
+}
+
+impl Error for ExportError {}
+
+impl Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportError::InvalidPageRange => write!(f, "invalid page range"),
+            ExportError::InvalidTimestamp => write!(f, "invalid timestamp"),
+        }
+    }
+}
+
+impl Debug for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+#[derive(Default)]
+pub struct PdfExportOptions {
+    /// Comma-separated, one-based page ranges such as `1-3,7` or `5-`.
+    /// All pages are exported when unset. Ranges that select no page are
+    /// rejected.
+    pub page_ranges: Option<String>,
+    /// A stable identifier for the PDF. Derived from the document when unset.
+    pub identifier: Option<String>,
+    /// The creation timestamp, in seconds since the Unix epoch.
+    pub timestamp: Option<i64>,
+}
+
+impl PdfExportOptions {
+    pub(crate) fn export(&self, document: &Document) -> Result<Vec<u8>, ExportError> {
+        let timestamp = self.timestamp.map(timestamp_to_datetime).transpose()?;
+        let ident = match &self.identifier {
+            Some(identifier) => Smart::Custom(identifier.as_str()),
+            None => Smart::Auto,
+        };
+
+        let Some(page_ranges) = &self.page_ranges else {
+            return Ok(typst_pdf::pdf(document, ident, timestamp));
+        };
+
+        let ranges = parse_page_ranges(page_ranges)?;
+        let mut selected = Document {
+            pages: document
+                .pages
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| ranges.iter().any(|range| range.contains(&(index + 1))))
+                .map(|(_, page)| page.clone())
+                .collect(),
+            ..document.clone()
+        };
+
+        if selected.pages.is_empty() {
+            return Err(ExportError::InvalidPageRange);
+        }
+
+        // Outline entries and links are resolved through the introspector, which
+        // must number the selected pages. Those pointing to pages that were left
+        // out are dropped.
+        selected.introspector.rebuild(&selected.pages);
+
+        Ok(typst_pdf::pdf(&selected, ident, timestamp))
+    }
+}
+
+/// Parses page ranges such as `1-3,7`, `5-` or `-2`.
+fn parse_page_ranges(ranges: &str) -> Result<Vec<RangeInclusive<usize>>, ExportError> {
+    let parse_page = |page: &str| -> Result<usize, ExportError> {
+        match page.trim().parse::<usize>() {
+            Ok(page) if page > 0 => Ok(page),
+            _ => Err(ExportError::InvalidPageRange),
+        }
+    };
+
+    ranges
+        .split(',')
+        .map(|range| {
+            let range = match range.split_once('-') {
+                Some((start, end)) => {
+                    let start = if start.trim().is_empty() {
+                        1
+                    } else {
+                        parse_page(start)?
+                    };
+                    let end = if end.trim().is_empty() {
+                        usize::MAX
+                    } else {
+                        parse_page(end)?
+                    };
+                    start..=end
+                }
+                None => {
+                    let page = parse_page(range)?;
+                    page..=page
+                }
+            };
+
+            if range.is_empty() {
+                Err(ExportError::InvalidPageRange)
+            } else {
+                Ok(range)
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn timestamp_to_datetime(timestamp: i64) -> Result<Datetime, ExportError> {
+    let datetime =
+        chrono::DateTime::from_timestamp(timestamp, 0).ok_or(ExportError::InvalidTimestamp)?;
+
+    Datetime::from_ymd_hms(
+        datetime.year(),
+        datetime.month() as u8,
+        datetime.day() as u8,
+        datetime.hour() as u8,
+        datetime.minute() as u8,
+        datetime.second() as u8,
+    )
+    .ok_or(ExportError::InvalidTimestamp)
+}
+
+impl TypstCompiler {
+    pub fn compile_with_options(
+        &self,
+        mut options: PdfExportOptions,
+    ) -> Result<CompilationResult, ExportError> {
+        options.timestamp = options.timestamp.or(self.pinned_timestamp());
+
+        let Typeset {
+            result,
+            dependencies,
+        } = self.typeset();
+
+        Ok(match result {
+            Ok((document, warnings)) => CompilationResult::Document {
+                data: options.export(&document)?,
+                warnings,
+                dependencies,
+            },
+            Err(errors) => CompilationResult::Errors {
+                errors,
+                dependencies,
+            },
+        })
+    }
+}
+
+impl CompiledDocument {
+    pub fn export_pdf_with_options(
+        &self,
+        mut options: PdfExportOptions,
+    ) -> Result<Vec<u8>, ExportError> {
+        options.timestamp = options.timestamp.or(self.timestamp);
+        options.export(&self.document)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_page_ranges;
+
+    #[test]
+    fn parses_single_pages_and_ranges() {
+        assert_eq!(parse_page_ranges("1-3,7").unwrap(), vec![1..=3, 7..=7]);
+        assert_eq!(
+            parse_page_ranges(" 2 - 4 , 6 ").unwrap(),
+            vec![2..=4, 6..=6]
+        );
+        assert_eq!(parse_page_ranges("3-3").unwrap(), vec![3..=3]);
+    }
+
+    #[test]
+    fn parses_open_ranges() {
+        assert_eq!(parse_page_ranges("5-").unwrap(), vec![5..=usize::MAX]);
+        assert_eq!(parse_page_ranges("-2").unwrap(), vec![1..=2]);
+        assert_eq!(parse_page_ranges("-").unwrap(), vec![1..=usize::MAX]);
+    }
+
+    #[test]
+    fn rejects_invalid_ranges() {
+        for ranges in ["", "0", "0-2", "3-1", "a", "1-b", "1,,2", "1-2-3", "-0"] {
+            assert!(parse_page_ranges(ranges).is_err(), "{ranges:?}");
+        }
+    }
+}
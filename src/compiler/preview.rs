@@ -0,0 +1,101 @@
+use typst::util::hash128;
+
+use crate::FileReference;
+
+use super::{
+    compile::{CompilationError, Typeset},
+    render::RenderedPage,
+    TypstCompiler,
+};
+
+/// Frame hashes of the last preview, used to skip re-rendering unchanged pages.
+#[derive(Default)]
+pub(crate) struct PreviewCache {
+    pixels_per_point: f32,
+    hashes: Vec<u128>,
+}
+
+pub struct PreviewPage {
+    pub index: u64,
+    pub page: RenderedPage,
+}
+
+pub enum PreviewResult {
+    Pages {
+        changed_pages: Vec<PreviewPage>,
+        page_count: u64,
+        warnings: Vec<CompilationError>,
+        dependencies: Vec<FileReference>,
+    },
+    Errors {
+        errors: Vec<CompilationError>,
+        dependencies: Vec<FileReference>,
+    },
+}
+
+impl TypstCompiler {
+    /// Compiles the main file and renders only the pages whose frames changed
+    /// since the previous preview.
+    pub fn compile_preview(&self, pixels_per_point: f32) -> PreviewResult {
+        let Typeset {
+            result,
+            dependencies,
+        } = self.typeset();
+
+        let (document, warnings) = match result {
+            Ok(result) => result,
+            Err(errors) => {
+                return PreviewResult::Errors {
+                    errors,
+                    dependencies,
+                }
+            }
+        };
+
+        let Ok(mut cache) = self.preview.write() else {
+            panic!("Failed to lock preview cache.")
+        };
+
+        // A different scale invalidates every page.
+        if cache.pixels_per_point != pixels_per_point {
+            cache.pixels_per_point = pixels_per_point;
+            cache.hashes.clear();
+        }
+
+        let hashes: Vec<u128> = document
+            .pages
+            .iter()
+            .map(|page| hash128(&page.frame))
+            .collect();
+
+        let changed_pages = document
+            .pages
+            .iter()
+            .zip(&hashes)
+            .enumerate()
+            .filter(|(index, (_, hash))| cache.hashes.get(*index) != Some(*hash))
+            .map(|(index, (page, _))| PreviewPage {
+                index: index as u64,
+                page: RenderedPage::from_frame(&page.frame, pixels_per_point),
+            })
+            .collect();
+
+        cache.hashes = hashes;
+
+        PreviewResult::Pages {
+            changed_pages,
+            page_count: document.pages.len() as u64,
+            warnings,
+            dependencies,
+        }
+    }
+
+    /// Forgets the previous preview, so that the next one renders every page.
+    pub fn reset_preview(&self) {
+        let Ok(mut cache) = self.preview.write() else {
+            panic!("Failed to lock preview cache.")
+        };
+
+        *cache = PreviewCache::default();
+    }
+}
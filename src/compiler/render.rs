@@ -0,0 +1,69 @@
+use typst::{layout::Frame, visualize::Color};
+
+use crate::{st_log, FileReference};
+
+use super::{
+    compile::{CompilationError, Typeset},
+    TypstCompiler,
+};
+
+pub struct RenderedPage {
+    pub data: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl RenderedPage {
+    /// Rasterizes a single page frame into a PNG image.
+    pub(crate) fn from_frame(frame: &Frame, pixels_per_point: f32) -> Self {
+        let pixmap = typst_render::render(frame, pixels_per_point, Color::WHITE);
+
+        let data = pixmap.encode_png().unwrap_or_else(|e| {
+            st_log!("Error encoding PNG: {}.", e);
+            vec![]
+        });
+
+        Self {
+            data,
+            width: pixmap.width(),
+            height: pixmap.height(),
+        }
+    }
+}
+
+pub enum PngCompilationResult {
+    Pages {
+        pages: Vec<RenderedPage>,
+        warnings: Vec<CompilationError>,
+        dependencies: Vec<FileReference>,
+    },
+    Errors {
+        errors: Vec<CompilationError>,
+        dependencies: Vec<FileReference>,
+    },
+}
+
+impl TypstCompiler {
+    pub fn compile_png(&self, pixels_per_point: f32) -> PngCompilationResult {
+        let Typeset {
+            result,
+            dependencies,
+        } = self.typeset();
+
+        match result {
+            Ok((document, warnings)) => PngCompilationResult::Pages {
+                pages: document
+                    .pages
+                    .iter()
+                    .map(|page| RenderedPage::from_frame(&page.frame, pixels_per_point))
+                    .collect(),
+                warnings,
+                dependencies,
+            },
+            Err(errors) => PngCompilationResult::Errors {
+                errors,
+                dependencies,
+            },
+        }
+    }
+}
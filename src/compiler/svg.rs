@@ -0,0 +1,51 @@
+use typst::layout::Abs;
+
+use crate::FileReference;
+
+use super::{
+    compile::{CompilationError, Typeset},
+    TypstCompiler,
+};
+
+pub enum SvgCompilationResult {
+    Pages {
+        pages: Vec<String>,
+        merged: Option<String>,
+        warnings: Vec<CompilationError>,
+        dependencies: Vec<FileReference>,
+    },
+    Errors {
+        errors: Vec<CompilationError>,
+        dependencies: Vec<FileReference>,
+    },
+}
+
+impl TypstCompiler {
+    /// Compiles the main file into one SVG image per page.
+    ///
+    /// When `merged_page_gap` is set, a single SVG containing all pages
+    /// separated by that many points is returned as well.
+    pub fn compile_svg(&self, merged_page_gap: Option<f64>) -> SvgCompilationResult {
+        let Typeset {
+            result,
+            dependencies,
+        } = self.typeset();
+
+        match result {
+            Ok((document, warnings)) => SvgCompilationResult::Pages {
+                pages: document
+                    .pages
+                    .iter()
+                    .map(|page| typst_svg::svg(&page.frame))
+                    .collect(),
+                merged: merged_page_gap.map(|gap| typst_svg::svg_merged(&document, Abs::pt(gap))),
+                warnings,
+                dependencies,
+            },
+            Err(errors) => SvgCompilationResult::Errors {
+                errors,
+                dependencies,
+            },
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+
+use typst::{
+    syntax::{FileId, VirtualPath},
+    World,
+};
+
+use super::TypstCompiler;
+
+pub enum Tooltip {
+    Text { text: String },
+    Code { code: String },
+}
+
+impl From<typst_ide::Tooltip> for Tooltip {
+    fn from(value: typst_ide::Tooltip) -> Self {
+        match value {
+            typst_ide::Tooltip::Text(text) => Self::Text {
+                text: text.to_string(),
+            },
+            typst_ide::Tooltip::Code(code) => Self::Code {
+                code: code.to_string(),
+            },
+        }
+    }
+}
+
+impl TypstCompiler {
+    pub fn tooltip(&self, file_path: String, line: u64, column: u64) -> Option<Tooltip> {
+        let document = self.document.read().ok()?;
+        let world = self.world.write().ok()?;
+
+        let id = FileId::new(None, VirtualPath::new(PathBuf::from(file_path)));
+        let source = world.source(id).ok()?;
+        let position = source.line_column_to_byte(line as usize, column as usize)?;
+
+        typst_ide::tooltip(&(*world), document.as_ref(), &source, position).map(Into::into)
+    }
+}
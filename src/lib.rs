@@ -4,14 +4,28 @@ mod cli_glue;
 mod compiler;
 mod utilities;
 
-pub use cli_glue::file_manager::{FileManager, FileManagerError};
+pub use cli_glue::directory_file_manager::DirectoryFileManager;
+pub use cli_glue::file_manager::{DirectoryEntry, FileManager, FileManagerError};
 pub use cli_glue::fonts::FontDefinition;
+pub use cli_glue::in_memory_file_manager::InMemoryFileManager;
 pub use compiler::{
     autocomplete::{AutocompleteKind, AutocompleteResult},
+    clock::ClockError,
     compile::{CompilationError, CompilationResult},
+    document::{CompiledDocument, DocumentCompilationResult, PageSize},
+    highlight::{HighlightTag, HighlightToken, HighlightUpdate},
+    jump::{DocumentPosition, Jump},
+    pdf::{ExportError, PdfExportOptions},
+    preview::{PreviewPage, PreviewResult},
+    render::{PngCompilationResult, RenderedPage},
+    svg::SvgCompilationResult,
+    tooltip::Tooltip,
     TypstCompiler,
 };
-pub use utilities::{source_location::SourceLocation, source_range::SourceRange};
+pub use utilities::{
+    file_reference::FileReference, snippet::SnippetSegment, source_location::SourceLocation,
+    source_range::SourceRange,
+};
 
 pub use typst::diag::{FileError, Severity};
 
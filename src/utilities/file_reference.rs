@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use typst::syntax::{FileId, VirtualPath};
+
+use crate::cli_glue::file_manager::parse_package;
+
+/// A file of the project, or of a package when `package` is set.
+pub struct FileReference {
+    pub path: String,
+    pub package: Option<String>,
+}
+
+impl FileReference {
+    /// Returns `None` if the package specification is invalid. The leading
+    /// `@` of the package is optional.
+    pub(crate) fn to_file_id(&self) -> Option<FileId> {
+        let package = match &self.package {
+            Some(package) => Some(parse_package(package)?),
+            None => None,
+        };
+
+        Some(FileId::new(
+            package,
+            VirtualPath::new(PathBuf::from(&self.path)),
+        ))
+    }
+}
+
+impl From<FileId> for FileReference {
+    fn from(value: FileId) -> Self {
+        Self {
+            path: value.vpath().as_rooted_path().to_string_lossy().to_string(),
+            package: value.package().map(|p| p.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FileReference;
+
+    fn package_of(package: &str) -> Option<String> {
+        let reference = FileReference {
+            path: "lib.typ".into(),
+            package: Some(package.into()),
+        };
+
+        Some(reference.to_file_id()?.package()?.to_string())
+    }
+
+    #[test]
+    fn accepts_packages_with_and_without_at() {
+        assert_eq!(package_of("@preview/x:1.0.0").unwrap(), "@preview/x:1.0.0");
+        assert_eq!(package_of("preview/x:1.0.0").unwrap(), "@preview/x:1.0.0");
+        assert!(package_of("preview/x").is_none());
+    }
+}
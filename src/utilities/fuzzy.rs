@@ -0,0 +1,85 @@
+/// Scores how well `candidate` matches `pattern`, or returns `None` when the
+/// characters of `pattern` do not all appear in order in `candidate`.
+///
+/// Matching is case-insensitive, but exact case, matches at the start of the
+/// candidate or of one of its words, and consecutive matches score higher.
+/// Skipped characters, including those after the last match, are penalized
+/// slightly. An empty pattern matches every candidate equally.
+pub(crate) fn fuzzy_score(pattern: &str, candidate: &str) -> Option<i64> {
+    if pattern.is_empty() {
+        return Some(0);
+    }
+
+    let mut pattern = pattern.chars().peekable();
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut previous_matched = false;
+
+    for c in candidate.chars() {
+        let Some(&p) = pattern.peek() else {
+            score -= 1;
+            continue;
+        };
+
+        if c.to_lowercase().eq(p.to_lowercase()) {
+            score += 1;
+
+            if c == p {
+                score += 1;
+            }
+
+            if previous_matched {
+                score += 8;
+            }
+
+            score += match previous {
+                None => 10,
+                Some(before) if !before.is_alphanumeric() => 6,
+                Some(before) if before.is_lowercase() && c.is_uppercase() => 6,
+                _ => 0,
+            };
+
+            pattern.next();
+            previous_matched = true;
+        } else {
+            score -= 1;
+            previous_matched = false;
+        }
+
+        previous = Some(c);
+    }
+
+    pattern.peek().is_none().then_some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fuzzy_score;
+
+    #[test]
+    fn requires_all_characters_in_order() {
+        assert!(fuzzy_score("abc", "aXbXc").is_some());
+        assert!(fuzzy_score("abc", "acb").is_none());
+        assert!(fuzzy_score("abcd", "abc").is_none());
+    }
+
+    #[test]
+    fn empty_pattern_matches_equally() {
+        assert_eq!(fuzzy_score("", "x"), Some(0));
+        assert_eq!(fuzzy_score("", "xml"), Some(0));
+    }
+
+    #[test]
+    fn exact_match_beats_longer_labels() {
+        assert!(fuzzy_score("x", "x") > fuzzy_score("x", "xml"));
+        assert!(fuzzy_score("text", "text") > fuzzy_score("text", "textfield"));
+    }
+
+    #[test]
+    fn prefers_case_consecutive_and_word_start_matches() {
+        assert!(fuzzy_score("Te", "Text") > fuzzy_score("Te", "text"));
+        assert!(fuzzy_score("ab", "abxx") > fuzzy_score("ab", "axbx"));
+        assert!(fuzzy_score("b", "a-b") > fuzzy_score("b", "acb"));
+        assert!(fuzzy_score("fb", "fooBar") > fuzzy_score("fb", "foobar"));
+    }
+}
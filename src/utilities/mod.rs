@@ -1,2 +1,5 @@
+pub mod file_reference;
+pub mod fuzzy;
+pub mod snippet;
 pub mod source_location;
 pub mod source_range;
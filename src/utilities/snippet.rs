@@ -0,0 +1,80 @@
+/// A piece of a completion snippet.
+///
+/// Placeholders are tab stops, visited in the order they appear in.
+pub enum SnippetSegment {
+    Text { text: String },
+    Placeholder { default_value: String },
+}
+
+impl SnippetSegment {
+    /// Splits Typst's snippet syntax (`${}` and `${name}`) into segments.
+    pub(crate) fn parse(snippet: &str) -> Vec<Self> {
+        let mut segments = vec![];
+        let mut rest = snippet;
+
+        while let Some(start) = rest.find("${") {
+            let Some(length) = rest[start + 2..].find('}') else {
+                break;
+            };
+
+            if start > 0 {
+                segments.push(Self::Text {
+                    text: rest[..start].to_string(),
+                });
+            }
+
+            segments.push(Self::Placeholder {
+                default_value: rest[start + 2..start + 2 + length].to_string(),
+            });
+
+            rest = &rest[start + 2 + length + 1..];
+        }
+
+        if !rest.is_empty() {
+            segments.push(Self::Text {
+                text: rest.to_string(),
+            });
+        }
+
+        segments
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SnippetSegment;
+
+    /// Writes placeholders as `[default]`, to compare segments as a string.
+    fn parse(snippet: &str) -> Vec<String> {
+        SnippetSegment::parse(snippet)
+            .into_iter()
+            .map(|segment| match segment {
+                SnippetSegment::Text { text } => text,
+                SnippetSegment::Placeholder { default_value } => format!("[{default_value}]"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_text_without_placeholders() {
+        assert_eq!(parse("heading"), ["heading"]);
+        assert!(parse("").is_empty());
+    }
+
+    #[test]
+    fn parses_placeholders_between_text() {
+        assert_eq!(parse("rgb(${r}, ${})"), ["rgb(", "[r]", ", ", "[]", ")"]);
+    }
+
+    #[test]
+    fn parses_leading_and_adjacent_placeholders() {
+        assert_eq!(parse("${a}${b} c"), ["[a]", "[b]", " c"]);
+        assert_eq!(parse("${}"), ["[]"]);
+    }
+
+    #[test]
+    fn keeps_unterminated_placeholders_as_text() {
+        assert_eq!(parse("a ${b"), ["a ${b"]);
+        assert_eq!(parse("${a} ${"), ["[a]", " ${"]);
+    }
+}
@@ -1,5 +1,6 @@
 use typst::syntax::Source;
 
+#[derive(Clone)]
 pub struct SourceLocation {
     pub byte_offset: u64,
     pub line: u64,
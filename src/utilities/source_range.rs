@@ -4,6 +4,7 @@ use typst::syntax::Source;
 
 use super::source_location::SourceLocation;
 
+#[derive(Clone)]
 pub struct SourceRange {
     pub start: SourceLocation,
     pub end: SourceLocation,